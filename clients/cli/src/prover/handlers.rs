@@ -1,17 +1,20 @@
 //! High-level proving interface
 
-use super::pipeline::ProvingPipeline;
+use super::pipeline::{ProofPayload, ProvingPipeline};
 use super::types::ProverError;
 use crate::environment::Environment;
 use crate::task::Task;
-use nexus_sdk::stwo::seq::Proof;
 
-/// Proves a program with authenticated task inputs
+/// Proves a program with authenticated task inputs.
+///
+/// Returns `ProofPayload`s rather than bare `Proof`s: callers must match on
+/// `ProofPayload::{Full, HashOnly}` when submitting results to the
+/// orchestrator, since hash-only task types no longer carry a full `Proof`.
 pub async fn authenticated_proving(
     task: &Task,
     environment: &Environment,
     client_id: &str,
     num_workers: &usize,
-) -> Result<(Vec<Proof>, String, Vec<String>), ProverError> {
+) -> Result<(Vec<ProofPayload>, String, Vec<String>), ProverError> {
     ProvingPipeline::prove_authenticated(task, environment, client_id, num_workers).await
 }
@@ -0,0 +1,12 @@
+//! Proof generation: parsing inputs, driving the prover, and orchestrating
+//! multi-input tasks.
+
+mod engine;
+mod handlers;
+mod input;
+mod pipeline;
+mod registry;
+mod types;
+
+pub use handlers::authenticated_proving;
+pub use pipeline::ProofPayload;
@@ -1,14 +1,55 @@
 //! Proving pipeline that orchestrates the full proving process
 
-use super::engine::ProvingEngine;
-use super::input::InputParser;
+use super::engine::{ProvingEngine, PublicInputs};
+use super::registry::{GuestProgram, ProverRegistry};
 use super::types::ProverError;
 use crate::analytics::track_verification_failed;
 use crate::environment::Environment;
 use crate::task::Task;
-use futures::stream::{StreamExt, TryStreamExt};
 use nexus_sdk::stwo::seq::Proof;
+use rayon::ThreadPoolBuilder;
 use sha3::{Digest, Keccak256};
+use std::sync::{Arc, OnceLock};
+use tokio::runtime::Handle;
+
+/// Dedicated proving thread pool, built once from the first `num_workers`
+/// seen and reused across every task so the miner loop doesn't pay
+/// thread-creation/teardown cost on every submission.
+static PROVING_POOL: OnceLock<rayon::ThreadPool> = OnceLock::new();
+
+/// Public values a single child proof attests to, carried into proof
+/// aggregation so the aggregated proof can reference each input's triple
+/// inputs and result without re-deriving them from the raw proof bytes.
+#[derive(Clone)]
+struct AggregationInput {
+    /// The triple inputs and result this child proof's public values attest to.
+    public_inputs: PublicInputs,
+    /// Keccak hash of the child proof, used to bind the aggregation to the
+    /// exact proof it attests was verified.
+    proof_hash: String,
+}
+
+impl AggregationInput {
+    fn new(public_inputs: PublicInputs, proof: &Proof) -> Self {
+        Self {
+            public_inputs,
+            proof_hash: ProvingPipeline::generate_proof_hash(proof),
+        }
+    }
+}
+
+/// A proof result the pipeline hands back to the caller: either the full
+/// proof, or just its hash when the task type only needs to submit hashes
+/// (e.g. `ProofHash` / `AllProofHashes`). Batch verification still needs
+/// every full proof in memory at once regardless of task type, so this
+/// does not lower that peak; it avoids retaining and returning the full
+/// `Vec<Proof>` once verification has finished for task types that only
+/// ever needed the hashes.
+#[derive(Clone)]
+pub enum ProofPayload {
+    Full(Proof),
+    HashOnly(String),
+}
 
 /// Orchestrates the complete proving pipeline
 pub struct ProvingPipeline;
@@ -20,25 +61,22 @@ impl ProvingPipeline {
         environment: &Environment,
         client_id: &str,
         num_workers: &usize,
-    ) -> Result<(Vec<Proof>, String, Vec<String>), ProverError> {
-        match task.program_id.as_str() {
-            "fib_input_initial" => {
-                Self::prove_fib_task(task, environment, client_id, num_workers).await
-            }
-            _ => Err(ProverError::MalformedTask(format!(
-                "Unsupported program ID: {}",
-                task.program_id
-            ))),
-        }
+    ) -> Result<(Vec<ProofPayload>, String, Vec<String>), ProverError> {
+        let registry = ProverRegistry::with_defaults();
+        let program = registry.get(&task.program_id)?;
+        Self::prove_task(task, environment, client_id, num_workers, program).await
     }
 
-    /// Process fibonacci proving task with multiple inputs
-    async fn prove_fib_task(
+    /// Process a task with multiple inputs against the given guest program.
+    /// The streaming/sorting/hash-combining logic here is generic over
+    /// whichever program was selected for the task.
+    async fn prove_task(
         task: &Task,
         environment: &Environment,
         client_id: &str,
         num_workers: &usize,
-    ) -> Result<(Vec<Proof>, String, Vec<String>), ProverError> {
+        program: Arc<dyn GuestProgram>,
+    ) -> Result<(Vec<ProofPayload>, String, Vec<String>), ProverError> {
         let all_inputs = task.all_inputs();
 
         if all_inputs.is_empty() {
@@ -49,62 +87,186 @@ impl ProvingPipeline {
 
         let mut proof_hashes = Vec::new();
         let mut all_proofs: Vec<Proof> = Vec::new();
+        let mut aggregation_inputs: Vec<AggregationInput> = Vec::new();
 
         let all_inputs: Vec<Vec<u8>> = all_inputs.to_vec();
 
-        let stream = futures::stream::iter(all_inputs.into_iter().enumerate().map(
-            |(input_index, input_data)| {
-                async move {
-                    // Step 1: Parse and validate input
-                    let inputs = InputParser::parse_triple_input(&input_data)?;
-
-                    // Step 2: Generate and verify proof
-                    let proof =
-                        ProvingEngine::prove_and_validate(&inputs, task, environment, client_id)
-                            .await
-                            .map_err(|e| {
-                                match e {
-                                    ProverError::Stwo(_) | ProverError::GuestProgram(_) => {
-                                        // Track verification failure
-                                        let error_msg = format!("Input {}: {}", input_index, e);
-                                        tokio::spawn(track_verification_failed(
-                                            task.clone(),
-                                            error_msg.clone(),
-                                            environment.clone(),
-                                            client_id.to_string(),
-                                        ));
-                                        e
-                                    }
-                                    _ => e,
-                                }
-                            })?;
-
-                    // Step 3: Generate proof hash
-                    let proof_hash = Self::generate_proof_hash(&proof);
-                    Ok::<(Proof, String, usize), ProverError>((proof, proof_hash, input_index))
-                }
-            },
-        ));
-
-        let results: Vec<(Proof, String, usize)> =
-            match stream.buffer_unordered(*num_workers).try_collect().await {
-                Ok(res) => res,
-                Err(e) => {
-                    return Err(e);
-                }
-            };
+        // Proof generation is CPU-bound, so it runs on a dedicated worker
+        // pool sized from `num_workers` rather than the async executor.
+        // Jobs are tagged with their `input_index` and results are sorted
+        // back into order once every job reports in, same as before.
+        let pool = Self::proving_pool(*num_workers)?;
+        let handle = Handle::current();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        for (input_index, input_data) in all_inputs.into_iter().enumerate() {
+            let tx = tx.clone();
+            let program = Arc::clone(&program);
+            let task = task.clone();
+            let environment = environment.clone();
+            let client_id = client_id.to_string();
+            let handle = handle.clone();
+            pool.spawn(move || {
+                let result = Self::prove_one(
+                    &program,
+                    &task,
+                    &environment,
+                    &client_id,
+                    &handle,
+                    input_index,
+                    &input_data,
+                );
+                let _ = tx.send(result);
+            });
+        }
+        drop(tx);
+
+        let mut results: Vec<(Proof, PublicInputs, String, usize, AggregationInput)> = Vec::new();
+        while let Some(result) = rx.recv().await {
+            results.push(result?);
+        }
 
-        let mut results = results;
-        results.sort_by_key(|(_, _, index)| *index);
+        results.sort_by_key(|(_, _, _, index, _)| *index);
 
-        for (proof, hash, _) in results {
+        // Batch-verify all proofs together, amortizing fixed verifier setup
+        // across the whole input set instead of paying it once per proof.
+        let proofs_with_inputs: Vec<(Proof, PublicInputs)> = results
+            .iter()
+            .map(|(proof, public_inputs, _, _, _)| (proof.clone(), public_inputs.clone()))
+            .collect();
+
+        if let Err(failed_indices) = ProvingEngine::verify_batch(&proofs_with_inputs) {
+            for &input_index in &failed_indices {
+                let error_msg = format!("Input {}: proof failed batch verification", input_index);
+                tokio::spawn(track_verification_failed(
+                    task.clone(),
+                    error_msg,
+                    environment.clone(),
+                    client_id.to_string(),
+                ));
+            }
+            return Err(ProverError::Stwo(format!(
+                "{} of {} proofs failed batch verification",
+                failed_indices.len(),
+                proofs_with_inputs.len()
+            )));
+        }
+
+        // Batch verification above already needed every full proof at once,
+        // so this doesn't lower that peak. It does mean `ProofHash` /
+        // `AllProofHashes` tasks, which only ever need the hashes, stop
+        // retaining proof bytes once verification has finished instead of
+        // carrying them through to the return value.
+        let keep_full_proofs = !matches!(
+            task.task_type,
+            crate::nexus_orchestrator::TaskType::AllProofHashes
+                | crate::nexus_orchestrator::TaskType::ProofHash
+        );
+
+        for (proof, _, hash, _, aggregation_input) in results {
             proof_hashes.push(hash);
-            all_proofs.push(proof);
+            aggregation_inputs.push(aggregation_input);
+            if keep_full_proofs {
+                all_proofs.push(proof);
+            }
+        }
+
+        if task.task_type == crate::nexus_orchestrator::TaskType::Aggregated {
+            let aggregated = Self::aggregate_proofs(&all_proofs, &aggregation_inputs)?;
+            let aggregated_hash = Self::generate_proof_hash(&aggregated);
+            return Ok((
+                vec![ProofPayload::Full(aggregated)],
+                aggregated_hash.clone(),
+                vec![aggregated_hash],
+            ));
+        }
+
+        let final_proof_hash = Self::combine_proof_hashes(task.task_type.clone(), &proof_hashes);
+
+        let payloads = if keep_full_proofs {
+            all_proofs.into_iter().map(ProofPayload::Full).collect()
+        } else {
+            proof_hashes
+                .iter()
+                .cloned()
+                .map(ProofPayload::HashOnly)
+                .collect()
+        };
+
+        Ok((payloads, final_proof_hash, proof_hashes))
+    }
+
+    /// Get the process-wide proving thread pool, building it on first use.
+    /// The pool is sized from whichever `num_workers` is seen first and then
+    /// reused for the lifetime of the process; later calls with a different
+    /// `num_workers` keep using the already-built pool.
+    fn proving_pool(num_workers: usize) -> Result<&'static rayon::ThreadPool, ProverError> {
+        if let Some(pool) = PROVING_POOL.get() {
+            return Ok(pool);
         }
 
-        let final_proof_hash = Self::combine_proof_hashes(task, &proof_hashes);
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(num_workers)
+            .build()
+            .map_err(|e| {
+                ProverError::MalformedTask(format!("Failed to build proving thread pool: {}", e))
+            })?;
+
+        Ok(PROVING_POOL.get_or_init(|| pool))
+    }
+
+    /// Parse and prove a single input on the calling (dedicated proving) thread.
+    /// `program.prove` is async, but proving itself is CPU-bound, so it is
+    /// driven to completion with `block_on` here rather than awaited on the
+    /// async executor; `handle` lets the execution-failure tracker still be
+    /// spawned onto the Tokio runtime from this non-runtime thread.
+    fn prove_one(
+        program: &Arc<dyn GuestProgram>,
+        task: &Task,
+        environment: &Environment,
+        client_id: &str,
+        handle: &Handle,
+        input_index: usize,
+        input_data: &[u8],
+    ) -> Result<(Proof, PublicInputs, String, usize, AggregationInput), ProverError> {
+        let inputs = program.parse_input(input_data)?;
+
+        let (proof, public_inputs) = handle
+            .block_on(program.prove(&inputs, task, environment, client_id))
+            .map_err(|e| {
+                if let ProverError::Stwo(_) | ProverError::GuestProgram(_) = e {
+                    // Track proving-stage failure
+                    let error_msg = format!("Input {}: {}", input_index, e);
+                    handle.spawn(track_verification_failed(
+                        task.clone(),
+                        error_msg,
+                        environment.clone(),
+                        client_id.to_string(),
+                    ));
+                }
+                e
+            })?;
+
+        let proof_hash = Self::generate_proof_hash(&proof);
+        let aggregation_input = AggregationInput::new(public_inputs.clone(), &proof);
+        Ok((proof, public_inputs, proof_hash, input_index, aggregation_input))
+    }
+
+    /// Fold a batch of per-input proofs into a single proof attesting that each
+    /// child proof verified correctly, so the orchestrator can submit one proof
+    /// for the whole batch instead of N.
+    fn aggregate_proofs(
+        proofs: &[Proof],
+        inputs: &[AggregationInput],
+    ) -> Result<Proof, ProverError> {
+        if proofs.is_empty() {
+            return Err(ProverError::MalformedTask(
+                "No proofs to aggregate".to_string(),
+            ));
+        }
 
-        Ok((all_proofs, final_proof_hash, proof_hashes))
+        let aggregated = ProvingEngine::aggregate_proofs(proofs, inputs)?;
+        Ok(aggregated)
     }
 
     /// Generate hash for a proof
@@ -114,8 +276,11 @@ impl ProvingPipeline {
     }
 
     /// Combine multiple proof hashes based on task type
-    fn combine_proof_hashes(task: &Task, proof_hashes: &[String]) -> String {
-        match task.task_type {
+    fn combine_proof_hashes(
+        task_type: crate::nexus_orchestrator::TaskType,
+        proof_hashes: &[String],
+    ) -> String {
+        match task_type {
             crate::nexus_orchestrator::TaskType::AllProofHashes
             | crate::nexus_orchestrator::TaskType::ProofHash => {
                 Task::combine_proof_hashes(proof_hashes)
@@ -123,4 +288,46 @@ impl ProvingPipeline {
             _ => proof_hashes.first().cloned().unwrap_or_default(),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nexus_orchestrator::TaskType;
+
+    #[test]
+    fn combine_proof_hashes_combines_for_hash_reporting_task_types() {
+        let hashes = vec!["aaa".to_string(), "bbb".to_string()];
+
+        for task_type in [TaskType::AllProofHashes, TaskType::ProofHash] {
+            assert_eq!(
+                ProvingPipeline::combine_proof_hashes(task_type, &hashes),
+                Task::combine_proof_hashes(&hashes),
+            );
+        }
+    }
+
+    #[test]
+    fn combine_proof_hashes_returns_first_hash_for_other_task_types() {
+        let hashes = vec!["aaa".to_string(), "bbb".to_string()];
+
+        assert_eq!(
+            ProvingPipeline::combine_proof_hashes(TaskType::Aggregated, &hashes),
+            "aaa",
+        );
+    }
+
+    #[test]
+    fn combine_proof_hashes_handles_empty_input() {
+        assert_eq!(
+            ProvingPipeline::combine_proof_hashes(TaskType::Aggregated, &[]),
+            "",
+        );
+    }
+
+    #[test]
+    fn aggregate_proofs_rejects_empty_batch() {
+        let err = ProvingPipeline::aggregate_proofs(&[], &[]).unwrap_err();
+        assert!(matches!(err, ProverError::MalformedTask(_)));
+    }
+}
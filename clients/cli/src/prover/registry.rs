@@ -0,0 +1,107 @@
+//! Registry of guest programs the CLI knows how to prove, keyed by the
+//! orchestrator's `program_id`.
+
+use super::engine::{ProvingEngine, PublicInputs};
+use super::input::{InputParser, Inputs};
+use super::types::ProverError;
+use crate::environment::Environment;
+use crate::task::Task;
+use async_trait::async_trait;
+use nexus_sdk::stwo::seq::Proof;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A guest program the CLI can parse inputs for and generate proofs against.
+#[async_trait]
+pub trait GuestProgram: Send + Sync {
+    /// The orchestrator-assigned program ID this implementation handles.
+    fn program_id(&self) -> &str;
+
+    /// Parse a task's raw input bytes into this program's input type.
+    fn parse_input(&self, input_data: &[u8]) -> Result<Inputs, ProverError>;
+
+    /// Generate a proof for the parsed inputs.
+    async fn prove(
+        &self,
+        inputs: &Inputs,
+        task: &Task,
+        environment: &Environment,
+        client_id: &str,
+    ) -> Result<(Proof, PublicInputs), ProverError>;
+}
+
+/// The fibonacci guest program (`fib_input_initial`), the CLI's original
+/// proving target.
+struct FibInputInitial;
+
+#[async_trait]
+impl GuestProgram for FibInputInitial {
+    fn program_id(&self) -> &str {
+        "fib_input_initial"
+    }
+
+    fn parse_input(&self, input_data: &[u8]) -> Result<Inputs, ProverError> {
+        InputParser::parse_triple_input(input_data)
+    }
+
+    async fn prove(
+        &self,
+        inputs: &Inputs,
+        task: &Task,
+        environment: &Environment,
+        client_id: &str,
+    ) -> Result<(Proof, PublicInputs), ProverError> {
+        ProvingEngine::prove(inputs, task, environment, client_id).await
+    }
+}
+
+/// Looks up the `GuestProgram` implementation for a task's `program_id`, so
+/// the pipeline no longer needs to hardcode which programs it supports.
+pub struct ProverRegistry {
+    programs: HashMap<String, Arc<dyn GuestProgram>>,
+}
+
+impl ProverRegistry {
+    /// Build the registry with all guest programs the CLI ships with.
+    pub fn with_defaults() -> Self {
+        let mut registry = Self {
+            programs: HashMap::new(),
+        };
+        registry.register(FibInputInitial);
+        registry
+    }
+
+    /// Register a guest program under its own `program_id`.
+    pub fn register(&mut self, program: impl GuestProgram + 'static) {
+        self.programs
+            .insert(program.program_id().to_string(), Arc::new(program));
+    }
+
+    /// Look up the guest program registered for `program_id`.
+    pub fn get(&self, program_id: &str) -> Result<Arc<dyn GuestProgram>, ProverError> {
+        self.programs.get(program_id).cloned().ok_or_else(|| {
+            ProverError::MalformedTask(format!("Unsupported program ID: {}", program_id))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_resolves_known_program_id() {
+        let registry = ProverRegistry::with_defaults();
+        let program = registry
+            .get("fib_input_initial")
+            .expect("fib_input_initial should be registered by default");
+        assert_eq!(program.program_id(), "fib_input_initial");
+    }
+
+    #[test]
+    fn get_rejects_unknown_program_id() {
+        let registry = ProverRegistry::with_defaults();
+        let err = registry.get("does_not_exist").unwrap_err();
+        assert!(matches!(err, ProverError::MalformedTask(_)));
+    }
+}